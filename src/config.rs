@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    env,
+    fs,
+    io,
+    path::{Path, PathBuf},
+};
+
+// how long before a deadline to fire a reminder
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub enum LeadUnit {
+    Minutes,
+    Hours,
+    Days,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct LeadTime {
+    pub amount: u32,
+    pub unit: LeadUnit,
+}
+
+impl LeadTime {
+    pub fn as_chrono_duration(&self) -> chrono::Duration {
+        match self.unit {
+            LeadUnit::Minutes => chrono::Duration::minutes(self.amount as i64),
+            LeadUnit::Hours => chrono::Duration::hours(self.amount as i64),
+            LeadUnit::Days => chrono::Duration::days(self.amount as i64),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Config {
+    pub tasks_file: String,
+    pub icon_path: String,
+    pub reminder_lead_times: Vec<LeadTime>,
+    // postgres connection string; when unset tasks live in `tasks_file` instead.
+    // skip_serializing_if is required here: toml can't serialize a bare None,
+    // and this is unset on every fresh install's default config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub psql_connection: Option<String>,
+}
+
+impl Config {
+    fn defaults_for(dir: &Path) -> Self {
+        Config {
+            tasks_file: dir.join("tasks.json").display().to_string(),
+            icon_path: dir.join("icon.png").display().to_string(),
+            reminder_lead_times: vec![
+                LeadTime { amount: 1, unit: LeadUnit::Days },
+                LeadTime { amount: 1, unit: LeadUnit::Hours },
+                LeadTime { amount: 10, unit: LeadUnit::Minutes },
+            ],
+            psql_connection: None,
+        }
+    }
+}
+
+// TODO_DIR overrides the working dir; otherwise default to ~/todo
+fn working_dir() -> PathBuf {
+    if let Ok(dir) = env::var("TODO_DIR") {
+        return PathBuf::from(dir);
+    }
+    match env::home_dir() {
+        Some(path) => path.join("todo"),
+        None => PathBuf::from("/todo"),
+    }
+}
+
+// loads config.toml from the working dir, writing sensible defaults on first run;
+// TODO_PSQL overrides whatever connection string (or lack of one) is in the file
+pub fn load_or_init() -> io::Result<Config> {
+    let dir = working_dir();
+    let config_path = dir.join("config.toml");
+
+    let mut config = if let Ok(contents) = fs::read_to_string(&config_path) {
+        toml::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?
+    } else {
+        let config = Config::defaults_for(&dir);
+        fs::create_dir_all(&dir)?;
+        let serialised = toml::to_string_pretty(&config)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+        fs::write(&config_path, serialised)?;
+        config
+    };
+
+    if let Ok(connection_string) = env::var("TODO_PSQL") {
+        config.psql_connection = Some(connection_string);
+    }
+
+    Ok(config)
+}
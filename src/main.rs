@@ -1,10 +1,12 @@
-use chrono::{Local, NaiveDateTime, TimeZone};
+use chrono::{
+    DateTime, Datelike, Duration as ChronoDuration, Local, NaiveDate, NaiveDateTime, TimeZone,
+    Weekday,
+};
 use cursive::{views::TextView, Cursive, CursiveExt};
 use notify_rust::Notification;
 use serde::{Deserialize, Serialize};
-use cursive::views::{ScrollView, LinearLayout};
+use cursive::views::{ScrollView, SelectView};
 use std::{
-    env::home_dir,
     fs::{self, File, read_to_string, write},
     io::{self, Write},
     path::Path,
@@ -13,9 +15,14 @@ use std::{
 use tokio::{
     signal,
     sync::Mutex,
-    time::{Duration, sleep},
+    time::{sleep, Duration as SleepDuration},
 };
 
+mod config;
+mod store;
+use config::Config;
+use store::{JsonStore, PostgresStore, Store};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 enum Priority {
     Low,
@@ -29,27 +36,215 @@ struct Task {
     deadline: u64,
     priority: Priority,
     completed: bool,
+    // indices into the same tasks vec; this task won't fire until all of these are completed
+    #[serde(default)]
+    dependencies: Vec<usize>,
+    #[serde(default)]
+    time_entries: Vec<TimeEntry>,
+    #[serde(default)]
+    repeat: Option<Repeat>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+enum Unit {
+    Days,
+    Weeks,
+    Months,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+enum Repeat {
+    Every { n: u32, unit: Unit },
+}
+
+// advances a unix timestamp to its next occurrence under `repeat`, clamping month-end overflow
+// (e.g. Jan 31 + 1 month lands on Feb 28/29, not March). Returns None if the advanced local
+// time doesn't exist (e.g. it falls in a spring-forward DST gap).
+fn advance_deadline(deadline: u64, repeat: &Repeat) -> Option<u64> {
+    let current = Local.timestamp_opt(deadline as i64, 0).unwrap();
+    let next = match repeat {
+        Repeat::Every { n, unit: Unit::Days } => current + ChronoDuration::days(*n as i64),
+        Repeat::Every { n, unit: Unit::Weeks } => current + ChronoDuration::weeks(*n as i64),
+        Repeat::Every { n, unit: Unit::Months } => add_months(current, *n)?,
+    };
+    Some(next.timestamp() as u64)
+}
+
+fn last_day_of_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .unwrap()
+        .pred_opt()
+        .unwrap()
+        .day()
+}
+
+fn add_months(date_time: DateTime<Local>, months: u32) -> Option<DateTime<Local>> {
+    let naive = date_time.naive_local();
+    let total_months = naive.month0() as i32 + months as i32;
+    let year = naive.year() + total_months / 12;
+    let month = (total_months % 12) as u32 + 1;
+    let day = naive.day().min(last_day_of_month(year, month));
+
+    let new_naive = NaiveDate::from_ymd_opt(year, month, day)?.and_time(naive.time());
+    Local.from_local_datetime(&new_naive).single()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct TimeEntry {
+    logged_date: NaiveDate,
+    duration: Duration,
+}
+
+// hours/minutes pair with the invariant minutes < 60, normalized on construction
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+struct Duration {
+    hours: u32,
+    minutes: u32,
+}
+
+impl Duration {
+    fn new(hours: u32, minutes: u32) -> Self {
+        let total_minutes = hours * 60 + minutes;
+        Duration {
+            hours: total_minutes / 60,
+            minutes: total_minutes % 60,
+        }
+    }
+}
+
+impl std::ops::Add for Duration {
+    type Output = Duration;
+
+    fn add(self, other: Duration) -> Duration {
+        Duration::new(self.hours + other.hours, self.minutes + other.minutes)
+    }
+}
+
+// appends a time entry to the task's log
+#[allow(dead_code)]
+fn log_time(task: &mut Task, logged_date: NaiveDate, duration: Duration) {
+    task.time_entries.push(TimeEntry {
+        logged_date,
+        duration,
+    });
+}
+
+// sums all logged time entries for a task, normalizing as it goes
+#[allow(dead_code)]
+fn total_logged_time(task: &Task) -> Duration {
+    task.time_entries
+        .iter()
+        .fold(Duration::default(), |total, entry| total + entry.duration)
+}
+
+// three-color DFS state for cycle detection, see topological_order below
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VisitState {
+    White,
+    Grey,
+    Black,
+}
+
+#[derive(Debug)]
+struct CycleError {
+    path: Vec<usize>,
+}
+
+impl std::fmt::Display for CycleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "circular task dependency detected: {:?}", self.path)
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+// DFS three-color marking: white = unvisited, grey = on the current stack,
+// black = fully explored. Hitting a grey node means we've found a back-edge,
+// i.e. a cycle; the stack at that point is the cycle's path.
+// Tasks are pushed to `order` when they go black, i.e. only after all of their
+// dependencies, so `order` is already a valid topological ordering
+// (dependencies before dependents).
+fn topological_order(tasks: &[Task]) -> Result<Vec<usize>, CycleError> {
+    fn visit(
+        index: usize,
+        tasks: &[Task],
+        state: &mut [VisitState],
+        stack: &mut Vec<usize>,
+        order: &mut Vec<usize>,
+    ) -> Result<(), CycleError> {
+        match state[index] {
+            VisitState::Black => return Ok(()),
+            VisitState::Grey => {
+                let cycle_start = stack.iter().position(|&i| i == index).unwrap_or(0);
+                let mut path = stack[cycle_start..].to_vec();
+                path.push(index);
+                return Err(CycleError { path });
+            }
+            VisitState::White => {}
+        }
+
+        state[index] = VisitState::Grey;
+        stack.push(index);
+
+        if let Some(task) = tasks.get(index) {
+            for &dep in &task.dependencies {
+                if dep < tasks.len() {
+                    visit(dep, tasks, state, stack, order)?;
+                }
+            }
+        }
+
+        stack.pop();
+        state[index] = VisitState::Black;
+        order.push(index);
+        Ok(())
+    }
+
+    let mut state = vec![VisitState::White; tasks.len()];
+    let mut order = Vec::with_capacity(tasks.len());
+    let mut stack = Vec::new();
+
+    for index in 0..tasks.len() {
+        visit(index, tasks, &mut state, &mut stack, &mut order)?;
+    }
+
+    Ok(order)
+}
+
+// whether every prerequisite of `task` is marked completed; doesn't wait
+async fn dependencies_satisfied(tasks_arc: &Arc<Mutex<Vec<Task>>>, task: &Task) -> bool {
+    let guard = tasks_arc.lock().await;
+    task.dependencies
+        .iter()
+        .all(|&dep| guard.get(dep).map(|t| t.completed).unwrap_or(true))
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut siv = Cursive::new();
 
-    siv.add_layer(TextView::new("Hello World!\nPress q to quit."));
+    siv.add_layer(TextView::new(
+        "Hello World!\nPress q to quit. Press Enter on a task to mark it complete.",
+    ));
     siv.add_global_callback('q', |s| s.quit());
 
-    let working_dir = match home_dir() {
-        Some(path) => path.display().to_string() + "/todo/",
-        None => "/todo/".to_string(),
-    };
-    let tasks_location = working_dir.clone() + "tasks.json";
-    let icon_location = working_dir + "icon.png";
+    let config: Config = config::load_or_init()?;
+    let tasks_location = config.tasks_file.clone();
+    let icon_location = config.icon_path.clone();
     if !Path::new(&icon_location).exists() {
         println!("Icon not set");
     }
 
+    // JSON is the default; a psql connection string (file or TODO_PSQL) switches
+    // to the shared Postgres-backed store instead
+    let store: Box<dyn Store> = match &config.psql_connection {
+        Some(connection_string) => Box::new(PostgresStore::connect(connection_string).await?),
+        None => Box::new(JsonStore { path: tasks_location.clone() }),
+    };
+
     // set up arc-mutex to share with ctrlc exit handler
-    let tasks_init = load(&tasks_location)?;
+    let tasks_init = store.load().await?;
     println!("Loaded tasks: {:?}", tasks_init);
     let tasks_arc = Arc::new(Mutex::new(tasks_init));
 
@@ -64,78 +259,245 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tasks_arc.lock().await.push(task); */
 
     let tasks_guard = tasks_arc.lock().await;
-    let mut layout = LinearLayout::vertical();
-    for task in tasks_guard.iter() {
-        layout.add_child(TextView::new(task.content.clone()));
-    }
-    let scrollable = ScrollView::new(layout);
-    siv.add_layer(scrollable);
-    drop(tasks_guard);
+    let abort_handles: AbortHandles = Arc::new(Mutex::new(vec![None; tasks_guard.len()]));
 
-    // new block so the program doesn't hang
-    // limit the scope of the first lock
-    {
-        let tasks_guard = tasks_arc.lock().await;
-        let mut handles = Vec::new();
+    // render in dependency order rather than file order
+    let render_order =
+        topological_order(&tasks_guard).unwrap_or_else(|_| (0..tasks_guard.len()).collect());
+    let mut select = SelectView::<usize>::new();
+    for index in render_order {
+        select.add_item(tasks_guard[index].content.clone(), index);
+    }
 
-        for task in tasks_guard.iter() {
-            let task_clone = task.clone();
-            handles.push(tokio::spawn(timer(task_clone, icon_location.clone())));
-        }
+    let select_tasks_arc = tasks_arc.clone();
+    let select_abort_handles = abort_handles.clone();
+    let select_icon_location = icon_location.clone();
+    let select_lead_times = config.reminder_lead_times.clone();
+    select.set_on_submit(move |_siv, &index: &usize| {
+        let tasks_arc = select_tasks_arc.clone();
+        let abort_handles = select_abort_handles.clone();
+        let icon_location = select_icon_location.clone();
+        let lead_times = select_lead_times.clone();
+        // bridge the synchronous cursive callback into the async task state;
+        // block_in_place hands this thread's other work to another worker
+        // while we wait, so it's safe to nest inside the multi-thread runtime
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(complete_task(
+                &tasks_arc,
+                &abort_handles,
+                index,
+                icon_location,
+                lead_times,
+            ));
+        });
+    });
+    siv.add_layer(ScrollView::new(select));
 
-        for handle in handles {
-            let _ = handle.await;
-        }
+    // spawn a countdown/reminder task per loaded task; these run independently
+    // of the TUI on the tokio runtime, so siv.run() below doesn't wait on them
+    for (index, task) in tasks_guard.iter().enumerate() {
+        let task_clone = task.clone();
+        let handle = tokio::spawn(timer(
+            task_clone,
+            icon_location.clone(),
+            tasks_arc.clone(),
+            index,
+            config.reminder_lead_times.clone(),
+            abort_handles.clone(),
+        ));
+        abort_handles.lock().await[index] = Some(handle.abort_handle());
     }
+    drop(tasks_guard);
 
     siv.run();
 
     signal::ctrl_c().await.expect("Failed to listen for Ctrl+C");
     println!("Exiting");
-    let _ = save(&tasks_arc, &tasks_location).await;
+    let tasks_guard = tasks_arc.lock().await;
+    let _ = store.save(&tasks_guard).await;
 
     Ok(())
 }
 
+#[derive(Debug)]
+enum DateParseError {
+    InvalidFormat(String),
+    NotInFuture,
+    TooFarInFuture,
+}
+
+impl std::fmt::Display for DateParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DateParseError::InvalidFormat(raw) => write!(
+                f,
+                "Couldn't understand \"{}\" as a date. Try dd/mm/yyyy, \"tomorrow\", \"next friday 9am\", \"in 2 hours\", or a bare hour like \"18\".",
+                raw
+            ),
+            DateParseError::NotInFuture => write!(f, "Date must be in the future"),
+            DateParseError::TooFarInFuture => write!(
+                f,
+                "Are you sure you're going to be around that long?\nPlease enter a date within 100 years from now (that's generous enough, right?)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DateParseError {}
+
+// checks the resolved instant is between now and 100 years out, returning its unix timestamp
+fn finish(target: DateTime<Local>, now: DateTime<Local>) -> Result<u64, DateParseError> {
+    let timestamp = target.timestamp();
+    let now_timestamp = now.timestamp();
+    if timestamp < now_timestamp {
+        return Err(DateParseError::NotInFuture);
+    }
+    // reject anything over 100 years out, mostly so unix time doesn't have a chance to overflow
+    if timestamp > now_timestamp + 3153600000 {
+        return Err(DateParseError::TooFarInFuture);
+    }
+    Ok(timestamp as u64)
+}
+
+// a bare hour number means "the next time it is N:00", rolling over to tomorrow if N:00 already passed today
+fn next_hour_occurrence(hour: u32, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    if hour > 23 {
+        return None;
+    }
+    let today = now.date_naive();
+    let candidate = Local.from_local_datetime(&today.and_hms_opt(hour, 0, 0)?).single()?;
+    if candidate > now {
+        Some(candidate)
+    } else {
+        Local
+            .from_local_datetime(&(today + ChronoDuration::days(1)).and_hms_opt(hour, 0, 0)?)
+            .single()
+    }
+}
+
+fn parse_weekday_name(name: &str) -> Option<Weekday> {
+    Some(match name {
+        "monday" => Weekday::Mon,
+        "tuesday" => Weekday::Tue,
+        "wednesday" => Weekday::Wed,
+        "thursday" => Weekday::Thu,
+        "friday" => Weekday::Fri,
+        "saturday" => Weekday::Sat,
+        "sunday" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+// the next date (strictly after `from`) that falls on `target` weekday
+fn next_weekday_date(target: Weekday, from: chrono::NaiveDate) -> chrono::NaiveDate {
+    let today = from.weekday().num_days_from_monday() as i64;
+    let target_num = target.num_days_from_monday() as i64;
+    let mut diff = (target_num - today + 7) % 7;
+    if diff == 0 {
+        diff = 7;
+    }
+    from + ChronoDuration::days(diff)
+}
+
+// "9am", "9pm", "14:00", "9:30am" -> (hour, minute)
+fn parse_time_of_day(raw: &str) -> Option<(u32, u32)> {
+    let s = raw.trim().to_lowercase();
+    let is_am = s.ends_with("am");
+    let is_pm = s.ends_with("pm");
+    let digits = if is_am || is_pm { &s[..s.len() - 2] } else { &s[..] };
+
+    let (hour_str, minute_str) = match digits.split_once(':') {
+        Some((h, m)) => (h, m),
+        None => (digits, "0"),
+    };
+
+    let mut hour: u32 = hour_str.trim().parse().ok()?;
+    let minute: u32 = minute_str.trim().parse().ok()?;
+
+    if is_pm && hour < 12 {
+        hour += 12;
+    } else if is_am && hour == 12 {
+        hour = 0;
+    }
+
+    if hour > 23 || minute > 59 {
+        return None;
+    }
+    Some((hour, minute))
+}
+
+// "in 2 hours", "in 30 minutes", "in 3 days", "in 1 week"
+fn parse_relative_duration(rest: &str, now: DateTime<Local>) -> Result<u64, DateParseError> {
+    let invalid = || DateParseError::InvalidFormat(format!("in {}", rest));
+    let mut parts = rest.split_whitespace();
+    let amount: i64 = parts.next().and_then(|n| n.parse().ok()).ok_or_else(invalid)?;
+    let unit = parts.next().ok_or_else(invalid)?;
+
+    let delta = match unit.trim_end_matches('s') {
+        "minute" => ChronoDuration::minutes(amount),
+        "hour" => ChronoDuration::hours(amount),
+        "day" => ChronoDuration::days(amount),
+        "week" => ChronoDuration::weeks(amount),
+        _ => return Err(invalid()),
+    };
+
+    finish(now + delta, now)
+}
+
 // get unix timestamp of the given date in the local time zone
 // this doesn't take potential time zone shifts/daylight savings into account for long-term tasks but whatever
-fn timestamp_from_date(deadline: String) -> u64 {
-    let now_timestamp = Local::now().timestamp();
+//
+// accepts dd/mm/yyyy, dd/mm/yyyy HH:MM, "tomorrow", "next <weekday> [time]", "in <n> <unit>",
+// or a bare hour number meaning "the next time it's that hour"
+fn timestamp_from_date(deadline: String) -> Result<u64, DateParseError> {
+    let now = Local::now();
+    let trimmed = deadline.trim();
+    let invalid = || DateParseError::InvalidFormat(trimmed.to_string());
+
+    if let Ok(hour) = trimmed.parse::<u32>() {
+        let candidate = next_hour_occurrence(hour, now).ok_or_else(invalid)?;
+        return finish(candidate, now);
+    }
+
+    let lower = trimmed.to_lowercase();
+
+    if lower == "tomorrow" {
+        let naive = (now.date_naive() + ChronoDuration::days(1))
+            .and_hms_opt(0, 0, 0)
+            .ok_or_else(invalid)?;
+        let target = Local.from_local_datetime(&naive).single().ok_or_else(invalid)?;
+        return finish(target, now);
+    }
+
+    if let Some(rest) = lower.strip_prefix("in ") {
+        return parse_relative_duration(rest, now);
+    }
+
+    let tokens: Vec<&str> = lower.split_whitespace().collect();
+    if tokens.first() == Some(&"next") {
+        if let Some(weekday) = tokens.get(1).and_then(|w| parse_weekday_name(w)) {
+            let date = next_weekday_date(weekday, now.date_naive());
+            let (hour, minute) = match tokens.get(2) {
+                Some(time) => parse_time_of_day(time).ok_or_else(invalid)?,
+                None => (0, 0),
+            };
+            let naive = date.and_hms_opt(hour, minute, 0).ok_or_else(invalid)?;
+            let target = Local.from_local_datetime(&naive).single().ok_or_else(invalid)?;
+            return finish(target, now);
+        }
+    }
 
     // try parsing as full datetime: "dd/mm/yyyy HH:MM"
-    let parsed = NaiveDateTime::parse_from_str(deadline.trim(), "%d/%m/%Y %H:%M").or_else(|_| {
+    let parsed = NaiveDateTime::parse_from_str(trimmed, "%d/%m/%Y %H:%M").or_else(|_| {
         // fallback: append "00:00" and try again
         println!("Warning: No time provided or format was wrong. Defaulting to 00:00.");
-        let fallback = deadline.trim().to_owned() + " 00:00";
+        let fallback = trimmed.to_owned() + " 00:00";
         NaiveDateTime::parse_from_str(&fallback, "%d/%m/%Y %H:%M")
     });
 
-    let deadline_timestamp = match parsed {
-        Ok(date) => {
-            let datetime_local = Local
-                .from_local_datetime(&date)
-                .single()
-                .expect("Ambiguous or non-existent local time");
-
-            let timestamp = datetime_local.timestamp();
-            if timestamp < now_timestamp {
-                panic!("Date must be in the future");
-            } else if timestamp > now_timestamp + 3153600000 {
-                // panic if date provided is more than 100 years in the future
-                // mostly because unix time overflows after a while and 100 years is more than enough
-                // if someone finally figures out this immortality thing please tell me
-                panic!(
-                    "Are you sure you're going to be around that long?\nPlease enter a date within 100 years from now (that's generous enough, right?)"
-                );
-            }
-            timestamp
-        }
-        Err(e) => panic!(
-            "Failed to parse date! Expected format: dd/mm/yyyy or dd/mm/yyyy HH:MM\nError: {}",
-            e
-        ),
-    };
-    deadline_timestamp as u64
+    let date = parsed.map_err(|_| invalid())?;
+    let target = Local.from_local_datetime(&date).single().ok_or_else(invalid)?;
+    finish(target, now)
 }
 
 fn file_exists(path: &str, create: bool) -> Result<bool, io::Error> {
@@ -154,9 +516,8 @@ fn file_exists(path: &str, create: bool) -> Result<bool, io::Error> {
     Ok(true)
 }
 
-async fn save(arc: &Arc<Mutex<Vec<Task>>>, path: &str) -> io::Result<()> {
-    let guard = arc.lock().await;
-    let data = &serde_json::to_string(&*guard).unwrap();
+async fn save(tasks: &[Task], path: &str) -> io::Result<()> {
+    let data = serde_json::to_string(tasks).unwrap();
     let _ = file_exists(path, true)?;
     let _ = write(path, data)?;
     println!("Saved tasks");
@@ -182,27 +543,279 @@ fn load(path: &str) -> io::Result<Vec<Task>> {
         }
     };
     let deserialised = serde_json::from_str::<Vec<Task>>(&data)?;
+    if let Err(e) = topological_order(&deserialised) {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, e.to_string()));
+    }
     Ok(deserialised)
 }
 
+// per-task abort handles so completing a task can cancel its pending reminders;
+// indexed the same way as the tasks vec
+type AbortHandles = Arc<Mutex<Vec<Option<tokio::task::AbortHandle>>>>;
+
+// advances a recurring task's deadline and spawns a fresh countdown for it.
+// Shared by timer()'s natural-completion tail and complete_task(): a task
+// aborted from the TUI never reaches timer()'s own tail (abort() cancels it
+// wherever it's parked in a sleep), so the respawn has to happen out here.
+async fn respawn_recurring(
+    tasks_arc: &Arc<Mutex<Vec<Task>>>,
+    abort_handles: &AbortHandles,
+    index: usize,
+    icon_location: String,
+    lead_times: Vec<config::LeadTime>,
+) {
+    let mut guard = tasks_arc.lock().await;
+    if let Some(current) = guard.get_mut(index) {
+        if let Some(repeat) = current.repeat.clone() {
+            match advance_deadline(current.deadline, &repeat) {
+                Some(next_deadline) => {
+                    current.deadline = next_deadline;
+                    current.completed = false;
+                    let respawned = current.clone();
+                    drop(guard);
+                    let new_handle = tokio::spawn(timer(
+                        respawned,
+                        icon_location,
+                        tasks_arc.clone(),
+                        index,
+                        lead_times,
+                        abort_handles.clone(),
+                    ));
+                    abort_handles.lock().await[index] = Some(new_handle.abort_handle());
+                }
+                None => {
+                    // the advanced local time doesn't exist (DST gap); leave the
+                    // task completed rather than respawning with a bad deadline
+                    eprintln!(
+                        "Could not advance deadline for recurring task \"{}\"",
+                        current.content
+                    );
+                }
+            }
+        }
+    }
+}
+
+// marks a task completed, cancels any reminder still in flight for it, and
+// respawns it if it recurs
+async fn complete_task(
+    tasks_arc: &Arc<Mutex<Vec<Task>>>,
+    abort_handles: &AbortHandles,
+    index: usize,
+    icon_location: String,
+    lead_times: Vec<config::LeadTime>,
+) {
+    if let Some(task) = tasks_arc.lock().await.get_mut(index) {
+        task.completed = true;
+    }
+    if let Some(handle) = abort_handles.lock().await.get(index).and_then(Option::clone) {
+        handle.abort();
+    }
+    respawn_recurring(tasks_arc, abort_handles, index, icon_location, lead_times).await;
+}
+
+// sleeps until `target`, in chunks no longer than a year so we never hand an
+// out-of-range duration to tokio for deadlines far in the future
+async fn sleep_until(target: DateTime<Local>) {
+    const MAX_CHUNK: SleepDuration = SleepDuration::from_secs(60 * 60 * 24 * 365);
+    loop {
+        let now = Local::now();
+        if target <= now {
+            return;
+        }
+        let remaining = (target - now).to_std().unwrap_or(SleepDuration::ZERO);
+        sleep(remaining.min(MAX_CHUNK)).await;
+    }
+}
+
 // I have no idea what this box thing is yet
 // or + Send + Sync
 async fn timer(
     task: Task,
     icon_location: String,
+    tasks_arc: Arc<Mutex<Vec<Task>>>,
+    index: usize,
+    lead_times: Vec<config::LeadTime>,
+    abort_handles: AbortHandles,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let date_time = Local.timestamp_opt(task.deadline as i64, 0).unwrap();
+    let deadline = Local.timestamp_opt(task.deadline as i64, 0).unwrap();
     println!(
         "Starting countdown for task {} scheduled for {}",
-        task.content, date_time
+        task.content, deadline
     );
-    // just seeing if it works
-    let _ = sleep(Duration::from_secs(5)).await;
-    Notification::new()
-        .summary(&task.content)
-        .body("Time's up")
-        .icon(&icon_location)
-        .show()?;
+
+    // don't notify until every prerequisite task is completed
+    while !dependencies_satisfied(&tasks_arc, &task).await {
+        sleep(SleepDuration::from_secs(1)).await;
+    }
+
+    // one reminder per configured lead-time, plus the deadline itself; drop any
+    // lead-time that's already in the past by the time we get here
+    let now = Local::now();
+    let mut reminders: Vec<DateTime<Local>> = lead_times
+        .iter()
+        .map(|lead| deadline - lead.as_chrono_duration())
+        .filter(|instant| *instant > now)
+        .collect();
+    reminders.push(deadline);
+    reminders.sort();
+    reminders.dedup();
+
+    for instant in reminders {
+        sleep_until(instant).await;
+        let body = if instant >= deadline {
+            "Time's up".to_string()
+        } else {
+            format!("Due at {}", deadline.format("%d/%m/%Y %H:%M"))
+        };
+        Notification::new()
+            .summary(&task.content)
+            .body(&body)
+            .icon(&icon_location)
+            .show()?;
+    }
     println!("Done");
+
+    // recurring tasks don't get dropped once completed: advance the deadline
+    // and re-spawn the countdown instead. completed only gets here if it was
+    // already true when this timer was loaded (see respawn_recurring) — the
+    // TUI's own completion path goes through complete_task instead, since
+    // aborting this timer would otherwise cancel it before we get here.
+    let completed = tasks_arc.lock().await.get(index).map(|t| t.completed).unwrap_or(false);
+    if completed {
+        respawn_recurring(&tasks_arc, &abort_handles, index, icon_location, lead_times).await;
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task_with_deps(deps: Vec<usize>) -> Task {
+        Task {
+            content: "test".to_string(),
+            deadline: 0,
+            priority: Priority::Medium,
+            completed: false,
+            dependencies: deps,
+            time_entries: Vec::new(),
+            repeat: None,
+        }
+    }
+
+    #[test]
+    fn rejects_self_dependency() {
+        let tasks = vec![task_with_deps(vec![0])];
+        assert!(topological_order(&tasks).is_err());
+    }
+
+    #[test]
+    fn rejects_three_task_cycle() {
+        let tasks = vec![
+            task_with_deps(vec![1]),
+            task_with_deps(vec![2]),
+            task_with_deps(vec![0]),
+        ];
+        assert!(topological_order(&tasks).is_err());
+    }
+
+    #[test]
+    fn accepts_acyclic_chain() {
+        let tasks = vec![
+            task_with_deps(vec![]),
+            task_with_deps(vec![0]),
+            task_with_deps(vec![1]),
+        ];
+        let order = topological_order(&tasks).expect("should not be a cycle");
+        // each task must come after every one of its dependencies
+        for (position, &index) in order.iter().enumerate() {
+            for &dep in &tasks[index].dependencies {
+                let dep_position = order.iter().position(|&i| i == dep).unwrap();
+                assert!(dep_position < position);
+            }
+        }
+    }
+
+    #[test]
+    fn minutes_roll_over_into_hours() {
+        let total = Duration::new(0, 45) + Duration::new(0, 30);
+        assert_eq!(total, Duration::new(1, 15));
+        assert!(total.minutes < 60);
+    }
+
+    #[test]
+    fn total_logged_time_sums_and_normalizes() {
+        let mut task = task_with_deps(vec![]);
+        log_time(
+            &mut task,
+            NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            Duration::new(0, 45),
+        );
+        log_time(
+            &mut task,
+            NaiveDate::from_ymd_opt(2026, 1, 2).unwrap(),
+            Duration::new(0, 30),
+        );
+        assert_eq!(total_logged_time(&task), Duration::new(1, 15));
+    }
+
+    fn local_dt(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Local> {
+        Local
+            .from_local_datetime(&NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, min, 0).unwrap())
+            .single()
+            .unwrap()
+    }
+
+    #[test]
+    fn bare_hour_rolls_over_to_tomorrow_past_midnight() {
+        // it's 00:10, asking for "0" (00:00) should roll to tomorrow since today's 00:00 has passed
+        let now = local_dt(2026, 1, 15, 0, 10);
+        let next = next_hour_occurrence(0, now).unwrap();
+        assert_eq!(next.date_naive(), NaiveDate::from_ymd_opt(2026, 1, 16).unwrap());
+    }
+
+    #[test]
+    fn bare_hour_stays_today_if_still_upcoming() {
+        let now = local_dt(2026, 1, 15, 10, 0);
+        let next = next_hour_occurrence(18, now).unwrap();
+        assert_eq!(next.date_naive(), NaiveDate::from_ymd_opt(2026, 1, 15).unwrap());
+    }
+
+    #[test]
+    fn bare_hour_rejects_out_of_range() {
+        let now = local_dt(2026, 1, 15, 10, 0);
+        assert!(next_hour_occurrence(24, now).is_none());
+    }
+
+    #[test]
+    fn parses_relative_duration() {
+        let now = local_dt(2026, 1, 15, 10, 0);
+        let result = parse_relative_duration("2 hours", now).unwrap();
+        assert_eq!(result, (now + ChronoDuration::hours(2)).timestamp() as u64);
+    }
+
+    #[test]
+    fn rejects_dates_in_the_past() {
+        assert!(timestamp_from_date("01/01/2000".to_string()).is_err());
+    }
+
+    #[test]
+    fn monthly_repeat_clamps_jan_31_to_feb_28_on_non_leap_year() {
+        let jan_31 = local_dt(2026, 1, 31, 9, 0).timestamp() as u64;
+        let repeat = Repeat::Every { n: 1, unit: Unit::Months };
+        let advanced = advance_deadline(jan_31, &repeat).expect("not a DST gap");
+        let next = Local.timestamp_opt(advanced as i64, 0).unwrap();
+        assert_eq!(next.date_naive(), NaiveDate::from_ymd_opt(2026, 2, 28).unwrap());
+    }
+
+    #[test]
+    fn monthly_repeat_clamps_jan_31_to_feb_29_on_leap_year() {
+        let jan_31 = local_dt(2024, 1, 31, 9, 0).timestamp() as u64;
+        let repeat = Repeat::Every { n: 1, unit: Unit::Months };
+        let advanced = advance_deadline(jan_31, &repeat).expect("not a DST gap");
+        let next = Local.timestamp_opt(advanced as i64, 0).unwrap();
+        assert_eq!(next.date_naive(), NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+    }
+}
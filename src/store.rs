@@ -0,0 +1,212 @@
+use crate::{Priority, Task};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::io;
+use tokio::sync::Mutex;
+
+// pluggable persistence so tasks can live in the flat tasks.json or a shared
+// Postgres database, picked at startup based on config
+#[async_trait]
+pub trait Store: Send + Sync {
+    async fn load(&self) -> io::Result<Vec<Task>>;
+    async fn save(&self, tasks: &[Task]) -> io::Result<()>;
+}
+
+pub struct JsonStore {
+    pub path: String,
+}
+
+#[async_trait]
+impl Store for JsonStore {
+    async fn load(&self) -> io::Result<Vec<Task>> {
+        crate::load(&self.path)
+    }
+
+    async fn save(&self, tasks: &[Task]) -> io::Result<()> {
+        crate::save(tasks, &self.path).await
+    }
+}
+
+pub struct PostgresStore {
+    // tokio_postgres::Client needs `&mut self` for transactions, so it's kept
+    // behind a mutex the same way shared task state is elsewhere in this app
+    client: Mutex<tokio_postgres::Client>,
+}
+
+impl PostgresStore {
+    pub async fn connect(connection_string: &str) -> Result<Self, tokio_postgres::Error> {
+        let (client, connection) =
+            tokio_postgres::connect(connection_string, tokio_postgres::NoTls).await?;
+
+        // the connection object does the actual IO; it has to be driven on its own task
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                eprintln!("Postgres connection error: {}", e);
+            }
+        });
+
+        client
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS tasks (
+                    id SERIAL PRIMARY KEY,
+                    content TEXT NOT NULL,
+                    deadline TIMESTAMPTZ NOT NULL,
+                    priority TEXT NOT NULL,
+                    completed BOOLEAN NOT NULL,
+                    dependencies JSONB NOT NULL DEFAULT '[]',
+                    time_entries JSONB NOT NULL DEFAULT '[]',
+                    repeat JSONB
+                )",
+            )
+            .await?;
+
+        Ok(PostgresStore { client: Mutex::new(client) })
+    }
+}
+
+fn priority_to_str(priority: &Priority) -> &'static str {
+    match priority {
+        Priority::Low => "Low",
+        Priority::Medium => "Medium",
+        Priority::High => "High",
+    }
+}
+
+fn priority_from_str(raw: &str) -> Priority {
+    match raw {
+        "Low" => Priority::Low,
+        "High" => Priority::High,
+        _ => Priority::Medium,
+    }
+}
+
+fn row_to_task(row: &tokio_postgres::Row) -> io::Result<Task> {
+    let content: String = row.get(0);
+    let deadline: DateTime<Utc> = row.get(1);
+    let priority: String = row.get(2);
+    let completed: bool = row.get(3);
+    let dependencies: serde_json::Value = row.get(4);
+    let time_entries: serde_json::Value = row.get(5);
+    let repeat: Option<serde_json::Value> = row.get(6);
+
+    Ok(Task {
+        content,
+        deadline: deadline.timestamp() as u64,
+        priority: priority_from_str(&priority),
+        completed,
+        dependencies: serde_json::from_value(dependencies)?,
+        time_entries: serde_json::from_value(time_entries)?,
+        repeat: repeat.map(serde_json::from_value).transpose()?,
+    })
+}
+
+#[async_trait]
+impl Store for PostgresStore {
+    async fn load(&self) -> io::Result<Vec<Task>> {
+        let client = self.client.lock().await;
+        let rows = client
+            .query(
+                "SELECT content, deadline, priority, completed, dependencies, time_entries, repeat
+                 FROM tasks ORDER BY id",
+                &[],
+            )
+            .await
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+
+        let tasks = rows.iter().map(row_to_task).collect::<io::Result<Vec<Task>>>()?;
+
+        // same invariant the JSON backend enforces on load: a task graph with a
+        // dependency cycle refuses to come up rather than hanging forever
+        if let Err(e) = crate::topological_order(&tasks) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, e.to_string()));
+        }
+
+        Ok(tasks)
+    }
+
+    async fn save(&self, tasks: &[Task]) -> io::Result<()> {
+        let mut client = self.client.lock().await;
+        let transaction = client
+            .transaction()
+            .await
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+
+        // truncate and re-insert inside one transaction so a failed insert rolls
+        // back instead of leaving the table truncated with partial data
+        transaction
+            .batch_execute("TRUNCATE tasks")
+            .await
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+
+        for task in tasks {
+            let deadline = DateTime::<Utc>::from_timestamp(task.deadline as i64, 0)
+                .unwrap_or_else(Utc::now);
+            let dependencies = serde_json::to_value(&task.dependencies)?;
+            let time_entries = serde_json::to_value(&task.time_entries)?;
+            let repeat = task.repeat.as_ref().map(serde_json::to_value).transpose()?;
+            transaction
+                .execute(
+                    "INSERT INTO tasks (content, deadline, priority, completed, dependencies, time_entries, repeat)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7)",
+                    &[
+                        &task.content,
+                        &deadline,
+                        &priority_to_str(&task.priority),
+                        &task.completed,
+                        &dependencies,
+                        &time_entries,
+                        &repeat,
+                    ],
+                )
+                .await
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn priority_round_trips_through_str() {
+        for priority in [Priority::Low, Priority::Medium, Priority::High] {
+            let round_tripped = priority_from_str(priority_to_str(&priority));
+            assert_eq!(priority_to_str(&round_tripped), priority_to_str(&priority));
+        }
+    }
+
+    #[test]
+    fn priority_from_str_defaults_to_medium_for_unknown_input() {
+        assert_eq!(priority_to_str(&priority_from_str("bogus")), "Medium");
+    }
+
+    #[tokio::test]
+    async fn json_store_round_trips_tasks() {
+        let path = format!("/tmp/todo_store_test_{}.json", std::process::id());
+        let store = JsonStore { path: path.clone() };
+        let tasks = vec![Task {
+            content: "write tests".to_string(),
+            deadline: 0,
+            priority: Priority::High,
+            completed: false,
+            dependencies: Vec::new(),
+            time_entries: Vec::new(),
+            repeat: None,
+        }];
+
+        store.save(&tasks).await.unwrap();
+        let loaded = store.load().await.unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].content, "write tests");
+    }
+}